@@ -5,25 +5,150 @@
 // - Enums for representing different expression types
 // - Box for heap allocation and recursive data structures
 // - Derive(Debug) for easy printing and debugging
+// - Derive(Clone, PartialEq) so trees can be copied and compared, e.g. in tests
+// - Display for printing expressions back as C source
 //
 // Functionality:
-// - Models integer literals and binary operations (+, -, *, /)
+// - Models integer literals, unary negation and binary operations (+, -, *, /)
+// - Records constant sub-expressions replaced by their value during optimization
 // - Used by the parser and code generator to represent and process expressions
+// - Converts a parsed program back into C source (unparse)
 use crate::token::Token;
 
+/// Size of an `int` in bytes.
+/// Every value is computed in a 64-bit register and returned in rax, so `int` is 8 bytes wide.
+pub const INT_SIZE: i64 = 8;
+
 /// Expression node for the AST.
 /// 
 /// - Number: Represents an integer literal.
+/// - UnaryOp: Represents a unary operation (e.g., -) applied to a single operand.
 /// - BinaryOp: Represents a binary operation (e.g., +, -, *, /) with left and right operands.
-#[derive(Debug)]
+/// - Folded: A constant sub-expression replaced by its value during optimization (`-O1`).
+#[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
     /// Integer literal
     Number(i64),
+    /// Unary operation (e.g., -)
+    UnaryOp {
+        op: Token,           // Operator token (-)
+        operand: Box<Expr>,  // Operand (another Expr)
+    },
     /// Binary operation (e.g., +, -, *, /)
     BinaryOp {
         op: Token,           // Operator token (+, -, *, /)
         left: Box<Expr>,     // Left operand (another Expr)
         right: Box<Expr>,    // Right operand (another Expr)
     },
+    /// Constant sub-expression replaced by its value (`-O1`)
+    Folded {
+        value: i64,          // The computed value
+        original: Box<Expr>, // The expression it replaced, kept for annotations
+    },
+}
+
+
+impl Expr {
+    /// Binding strength of the expression's outermost operator; higher binds tighter.
+    /// Used to decide where parentheses are needed when printing.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Number(_) => 4,
+            Expr::UnaryOp { .. } => 3,
+            Expr::BinaryOp { op: Token::Star | Token::Slash, .. } => 2,
+            Expr::BinaryOp { .. } => 1,
+            Expr::Folded { original, .. } => original.precedence(),
+        }
+    }
+}
+
+impl std::fmt::Display for Expr {
+    /// Writes the expression as C source, e.g. `2 + 3 * 4`.
+    /// Parentheses are only added where precedence or left-associativity requires them.
+    /// A folded expression is written as the source it replaced.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{}", n),
+            Expr::Folded { original, .. } => write!(f, "{}", original),
+            Expr::UnaryOp { op, operand } => {
+                // Parenthesize nested negations so `- -x` doesn't print as `--x`
+                if operand.precedence() < 3 || matches!(**operand, Expr::UnaryOp { .. } | Expr::Number(i64::MIN..0)) {
+                    write!(f, "{}({})", op, operand)
+                } else {
+                    write!(f, "{}{}", op, operand)
+                }
+            }
+            Expr::BinaryOp { op, left, right } => {
+                let prec = self.precedence();
+                // The left operand needs parentheses only if it binds more loosely;
+                // the right one also at equal precedence, since operators are left-associative.
+                if left.precedence() < prec {
+                    write!(f, "({})", left)?;
+                } else {
+                    write!(f, "{}", left)?;
+                }
+                write!(f, " {} ", op)?;
+                if right.precedence() <= prec {
+                    write!(f, "({})", right)
+                } else {
+                    write!(f, "{}", right)
+                }
+            }
+        }
+    }
+}
+
+/// Regenerates C source for a program whose main function returns `expr`.
+///
+/// Parentheses are only inserted where precedence requires them. For trees produced by
+/// the parser, parsing the output again yields an equal tree; a hand-built negation of a
+/// literal such as `UnaryOp { -, Number(5) }` prints as `-5` and comes back as
+/// `Number(-5)`, since the parser folds negated literals. Enum constants have already been
+/// folded to numbers by the parser, so they are printed as their values.
+///
+/// # Arguments
+/// * `expr` - The expression returned by main.
+///
+/// # Returns
+/// The C source code of the program.
+pub fn unparse(expr: &Expr) -> String {
+    format!("int main() {{\n    return {};\n}}\n", expr)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_source;
+
+    #[test]
+    fn parsed_trees_compare_equal_to_hand_built_ones() {
+        assert_eq!(
+            parse_source("int main(){return 1+2;}"),
+            Expr::BinaryOp {
+                op: Token::Plus,
+                left: Box::new(Expr::Number(1)),
+                right: Box::new(Expr::Number(2)),
+            }
+        );
+    }
+
+    #[test]
+    fn unparsed_programs_parse_back_to_the_same_tree() {
+        for source in [
+            "int main() { return 1 + 2 * 3; }",
+            "int main() { return (1 + 2) * 3; }",
+            "int main() { return 1 - (2 - 3); }",
+            "int main() { return -(4 / -2) - -1; }",
+            "enum { A = 5 }; int main() { return -(A * 2); }",
+        ] {
+            let expr = parse_source(source);
+            assert_eq!(parse_source(&unparse(&expr)), expr, "{}", source);
+        }
+    }
+
+    #[test]
+    fn unparse_adds_only_the_parentheses_it_needs() {
+        let expr = parse_source("int main() { return (1) + ((2) * 3); }");
+        assert_eq!(unparse(&expr), "int main() {\n    return 1 + 2 * 3;\n}\n");
+    }
+}
@@ -0,0 +1,65 @@
+// This module defines the extension point for output generators ("backends").
+// Every backend turns the same parsed program into text; the driver picks one based on
+// the command-line options and does not need to know how each one works.
+//
+// Rust features used:
+// - Traits for a common interface over different generators
+// - Trait objects (Box<dyn Backend>) for selecting a backend at runtime
+//
+// Functionality:
+// - X86Backend: NASM x86_64 assembly (the default), implemented by codegen::generate_asm
+// - AstBackend: a textual dump of the AST (`--emit ast`)
+use crate::ast::Expr;
+use crate::codegen::{generate_asm_with_options, CodegenOptions};
+use crate::dump::{dump_ast, AstFormat};
+
+/// A generator that turns a parsed program into output text.
+pub trait Backend {
+    /// Generates the output for a program whose main function returns `expr`.
+    fn emit_program(&self, expr: &Expr) -> String;
+}
+
+/// The default backend: x86_64 assembly in NASM syntax.
+#[derive(Debug, Clone, Default)]
+pub struct X86Backend {
+    /// Options passed through to the code generator.
+    pub options: CodegenOptions,
+}
+
+impl Backend for X86Backend {
+    fn emit_program(&self, expr: &Expr) -> String {
+        generate_asm_with_options(expr, &self.options)
+    }
+}
+
+/// A backend that prints the AST instead of compiling it.
+#[derive(Debug, Clone)]
+pub struct AstBackend {
+    /// The format to render the tree in.
+    pub format: AstFormat,
+}
+
+impl Backend for AstBackend {
+    fn emit_program(&self, expr: &Expr) -> String {
+        dump_ast(expr, self.format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_source;
+
+    #[test]
+    fn backends_are_interchangeable_behind_the_trait() {
+        let expr = parse_source("int main() { return 1 + 2; }");
+        let backends: Vec<Box<dyn Backend>> = vec![
+            Box::new(X86Backend::default()),
+            Box::new(AstBackend { format: AstFormat::Sexpr }),
+        ];
+        let outputs: Vec<String> = backends.iter().map(|backend| backend.emit_program(&expr)).collect();
+        assert!(outputs[0].contains("add rax, rcx"));
+        assert_eq!(outputs[1], "(+ 1 2)");
+        assert_ne!(outputs[0], outputs[1]);
+    }
+}
@@ -10,12 +10,156 @@
 //
 // Functionality:
 // - Converts an arithmetic expression AST into assembly code
-// - Handles binary operations and integer literals
+// - Handles unary and binary operations and integer literals
 // - Produces a minimal Linux program that exits with the result of main()
 use crate::ast::Expr;
 use crate::token::Token;
 
-/// Generates x86_64 assembly code from an expression AST.
+/// Operating system ABI the generated program targets.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Target {
+    /// x86_64 Linux (ELF): plain symbol names, `exit` is syscall 60.
+    #[default]
+    Linux,
+    /// x86_64 macOS (Mach-O, experimental): C symbols get a leading underscore and
+    /// BSD syscalls are numbered from 0x2000000.
+    Darwin,
+}
+
+impl Target {
+    /// Parses a target triple as accepted by `--target`.
+    pub fn from_triple(triple: &str) -> Option<Target> {
+        match triple {
+            "x86_64-linux" | "x86_64-unknown-linux-gnu" => Some(Target::Linux),
+            "x86_64-apple-darwin" => Some(Target::Darwin),
+            _ => None,
+        }
+    }
+
+    /// The NASM output format (`-f`) for object files on this target.
+    pub fn nasm_format(self) -> &'static str {
+        match self {
+            Target::Linux => "elf64",
+            Target::Darwin => "macho64",
+        }
+    }
+
+    /// The assembly symbol name for a C function name.
+    fn symbol(self, name: &str) -> String {
+        match self {
+            Target::Linux => name.to_string(),
+            Target::Darwin => format!("_{}", name),
+        }
+    }
+
+    /// The label of the program entry point defined by the start stub.
+    /// On macOS this is passed to the linker with `-e start`.
+    fn entry(self) -> &'static str {
+        match self {
+            Target::Linux => "_start",
+            Target::Darwin => "start",
+        }
+    }
+
+    /// The syscall number of `exit`.
+    fn exit_syscall(self) -> &'static str {
+        match self {
+            Target::Linux => "60",
+            Target::Darwin => "0x2000001",
+        }
+    }
+}
+
+/// Assembler dialect of the generated code.
+///
+/// Both dialects use Intel operand order, so instructions are identical;
+/// only directives and comments differ.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Syntax {
+    /// NASM (`global`, `section .text`, `;` comments).
+    #[default]
+    Nasm,
+    /// GNU as in Intel mode (`.intel_syntax noprefix`, `.globl`, `.text`, `#` comments).
+    GasIntel,
+}
+
+impl Syntax {
+    /// Parses a syntax name as accepted by `--syntax`.
+    pub fn from_name(name: &str) -> Option<Syntax> {
+        match name {
+            "nasm" => Some(Syntax::Nasm),
+            "gas-intel" => Some(Syntax::GasIntel),
+            _ => None,
+        }
+    }
+
+    /// The conventional file extension for assembly in this dialect.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Syntax::Nasm => "asm",
+            Syntax::GasIntel => "s",
+        }
+    }
+
+    /// Directives that must come first in the file.
+    fn prologue(self) -> &'static str {
+        match self {
+            Syntax::Nasm => "",
+            Syntax::GasIntel => ".intel_syntax noprefix\n",
+        }
+    }
+
+    /// The directive exporting a symbol.
+    fn global(self) -> &'static str {
+        match self {
+            Syntax::Nasm => "global",
+            Syntax::GasIntel => ".globl",
+        }
+    }
+
+    /// The directive switching to the code section.
+    fn text_section(self) -> &'static str {
+        match self {
+            Syntax::Nasm => "section .text",
+            Syntax::GasIntel => ".text",
+        }
+    }
+
+    /// The comment character; in GAS, `;` separates statements instead.
+    fn comment(self) -> &'static str {
+        match self {
+            Syntax::Nasm => ";",
+            Syntax::GasIntel => "#",
+        }
+    }
+}
+
+/// Options controlling the shape of the generated assembly.
+#[derive(Debug, Clone)]
+pub struct CodegenOptions {
+    /// The operating system ABI to generate code for.
+    pub target: Target,
+    /// The assembler dialect to write.
+    pub syntax: Syntax,
+    /// Emit the `_start` stub that calls main and exits with its result.
+    /// Disable this when linking against a runtime (e.g. libc) that provides its own entry point.
+    pub emit_start: bool,
+    /// Annotate values folded by `-O1` with the expression they came from, e.g. `mov rax, 14 ; 2 + 3 * 4`.
+    pub fold_comments: bool,
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        CodegenOptions {
+            target: Target::Linux,
+            syntax: Syntax::Nasm,
+            emit_start: true,
+            fold_comments: false,
+        }
+    }
+}
+
+/// Generates x86_64 assembly code from an expression AST using the default options.
 /// 
 /// # Arguments
 /// * `expr` - The root of the AST representing the return value of main().
@@ -23,40 +167,90 @@ use crate::token::Token;
 /// # Returns
 /// A String containing the full assembly code for a minimal Linux program.
 pub fn generate_asm(expr: &Expr) -> String {
+    generate_asm_with_options(expr, &CodegenOptions::default())
+}
+
+/// Generates x86_64 assembly code from an expression AST.
+/// 
+/// # Arguments
+/// * `expr` - The root of the AST representing the return value of main().
+/// * `options` - Options controlling what is emitted around main.
+/// 
+/// # Returns
+/// A String containing the assembly code.
+pub fn generate_asm_with_options(expr: &Expr, options: &CodegenOptions) -> String {
     // Buffer to accumulate instructions for the main function
     let mut code = String::new();
     // Recursively generate code for the expression
-    gen_expr(expr, &mut code);
+    gen_expr(expr, &mut code, options);
     // Add the return instruction for main
     code.push_str("    ret\n");
 
+    // Symbol names follow the target's conventions (`_main` on macOS).
+    // NASM maps `.text` to the __TEXT,__text section when producing Mach-O.
+    let target = options.target;
+    let main = target.symbol("main");
+    // Directives follow the chosen assembler dialect
+    let syntax = options.syntax;
+    let prologue = syntax.prologue();
+    let global = syntax.global();
+    let text = syntax.text_section();
+
+    // Without the _start stub, only main is defined and exported
+    if !options.emit_start {
+        return format!(
+            "{prologue}{global} {main}\n        {text}\n\n        {main}:\n        {}",
+            code
+        );
+    }
+
     // The assembly includes:
     // - _start: entry point, calls main, exits with main's return value
     // - main: computes the result and returns it in rax
     format!(
-        "global _start\n        global main\n        section .text\n\n        _start:\n            call main\n            mov rdi, rax\n            mov rax, 60\n            syscall\n\n        main:\n        {}",
-            code
+        "{prologue}{global} {entry}\n        {global} {main}\n        {text}\n\n        {entry}:\n            call {main}\n            mov rdi, rax\n            mov rax, {exit}\n            syscall\n\n        {main}:\n        {}",
+            code,
+            entry = target.entry(),
+            exit = target.exit_syscall(),
     )
 }
 
 /// Recursively walks the AST and generates assembly instructions for each node.
-/// Handles numbers and binary operations (+, -, *, /).
+/// Handles numbers, unary negation and binary operations (+, -, *, /).
+/// A sub-expression folded by the optimizer is loaded as a single value.
 /// 
 /// # Arguments
 /// * `expr` - The AST node to generate code for.
 /// * `code` - Mutable string buffer to append instructions.
-fn gen_expr(expr: &Expr, code: &mut String) {
+/// * `options` - Codegen options (annotations).
+fn gen_expr(expr: &Expr, code: &mut String, options: &CodegenOptions) {
     match expr {
         // For a number literal, move its value into rax
         Expr::Number(n) => {
             code.push_str(&format!("    mov rax, {}\n", n));
         }
+        // A folded constant is loaded the same way, optionally noting where it came from
+        Expr::Folded { value, original } => {
+            if options.fold_comments {
+                code.push_str(&format!("    mov rax, {} {} {}\n", value, options.syntax.comment(), original));
+            } else {
+                code.push_str(&format!("    mov rax, {}\n", value));
+            }
+        }
+        // For a unary operation, evaluate the operand and apply the operator to rax
+        Expr::UnaryOp { op, operand } => {
+            gen_expr(operand, code, options);
+            match op {
+                Token::Minus => code.push_str("    neg rax\n"), // rax = -operand
+                _ => panic!("Unsupported operator: {:?}", op),
+            }
+        }
         // For a binary operation, recursively generate code for operands
         Expr::BinaryOp { op, left, right } => {
             // Evaluate right operand first and push its result onto the stack
-            gen_expr(right, code);         // Evaluate right expr and put result in rax
+            gen_expr(right, code, options); // Evaluate right expr and put result in rax
             code.push_str("    push rax\n"); // Save right operand to stack
-            gen_expr(left, code);          // Evaluate left expr and put result in rax
+            gen_expr(left, code, options); // Evaluate left expr and put result in rax
             code.push_str("    pop rcx\n");  // Restore right operand to rcx
 
             // Emit the appropriate instruction based on the operator
@@ -74,3 +268,73 @@ fn gen_expr(expr: &Expr, code: &mut String) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fold::fold_constants;
+    use crate::parser::parse_source;
+
+    /// Compiles a full program with the given options.
+    fn compile(source: &str, options: &CodegenOptions) -> String {
+        generate_asm_with_options(&parse_source(source), options)
+    }
+
+    /// Returns the instructions of main, trimmed, one per entry.
+    fn main_body(asm: &str) -> Vec<&str> {
+        let body = &asm[asm.find("main:\n").expect("main is defined") + "main:\n".len()..];
+        body.lines().map(str::trim).collect()
+    }
+
+    #[test]
+    fn negative_literal_is_a_single_mov() {
+        let asm = compile("int main() { return -5; }", &CodegenOptions::default());
+        assert_eq!(main_body(&asm), ["mov rax, -5", "ret"]);
+        let asm = compile("int main() { return -2147483648; }", &CodegenOptions::default());
+        assert_eq!(main_body(&asm), ["mov rax, -2147483648", "ret"]);
+    }
+
+    #[test]
+    fn no_start_emits_only_main() {
+        let options = CodegenOptions { emit_start: false, ..CodegenOptions::default() };
+        let asm = compile("int main() { return 2; }", &options);
+        assert!(!asm.contains("_start"));
+        assert_eq!(
+            asm,
+            "global main\n        section .text\n\n        main:\n            mov rax, 2\n    ret\n"
+        );
+    }
+
+    #[test]
+    fn folded_constants_are_annotated_with_their_source() {
+        let mut expr = parse_source("int main() { return 2 + 3 * 4; }");
+        fold_constants(&mut expr);
+        let options = CodegenOptions { fold_comments: true, ..CodegenOptions::default() };
+        let asm = generate_asm_with_options(&expr, &options);
+        assert_eq!(main_body(&asm), ["mov rax, 14 ; 2 + 3 * 4", "ret"]);
+        // `;` separates statements in GAS, so the annotation uses `#` there
+        let options = CodegenOptions { syntax: Syntax::GasIntel, ..options };
+        let asm = generate_asm_with_options(&expr, &options);
+        assert_eq!(main_body(&asm), ["mov rax, 14 # 2 + 3 * 4", "ret"]);
+    }
+
+    #[test]
+    fn darwin_uses_underscored_main_and_bsd_exit() {
+        let options = CodegenOptions { target: Target::Darwin, ..CodegenOptions::default() };
+        let asm = compile("int main() { return 2; }", &options);
+        assert_eq!(
+            asm,
+            "global start\n        global _main\n        section .text\n\n        start:\n            call _main\n            mov rdi, rax\n            mov rax, 0x2000001\n            syscall\n\n        _main:\n            mov rax, 2\n    ret\n"
+        );
+    }
+
+    #[test]
+    fn gas_intel_output_starts_with_the_syntax_directive() {
+        let options = CodegenOptions { syntax: Syntax::GasIntel, ..CodegenOptions::default() };
+        let asm = compile("int main() { return 2; }", &options);
+        assert!(asm.starts_with(".intel_syntax noprefix\n"));
+        assert!(asm.contains(".globl _start\n"));
+        assert!(asm.contains(".text\n"));
+        assert!(!asm.contains("section .text"));
+    }
+}
@@ -0,0 +1,114 @@
+// This module renders expression ASTs as text for tooling and debugging (`--emit ast`).
+// Both output formats share a single tree walk; only the way each node is written differs.
+//
+// Rust features used:
+// - Enums for selecting the output format
+// - Pattern matching for AST traversal
+// - String building with fmt::Write
+//
+// Functionality:
+// - S-expression output, e.g. `(+ 1 (* 2 3))`
+// - JSON output with one object per node
+use std::fmt::Write;
+
+use crate::ast::Expr;
+
+/// Output format for an AST dump.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AstFormat {
+    /// Compact Lisp-style form: `(+ 1 (* 2 3))`.
+    Sexpr,
+    /// JSON objects: `{"type":"Number","value":1}`.
+    Json,
+}
+
+impl AstFormat {
+    /// Parses a format name as accepted by `--ast-format`.
+    pub fn from_name(name: &str) -> Option<AstFormat> {
+        match name {
+            "sexpr" => Some(AstFormat::Sexpr),
+            "json" => Some(AstFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Renders an expression tree in the given format.
+///
+/// # Arguments
+/// * `expr` - The root of the AST.
+/// * `format` - The output format.
+///
+/// # Returns
+/// The rendered tree on a single line.
+pub fn dump_ast(expr: &Expr, format: AstFormat) -> String {
+    let mut out = String::new();
+    write_node(expr, format, &mut out);
+    out
+}
+
+/// Recursively writes a node and its children.
+///
+/// Operator nodes are described by their type name, operator and named children, which
+/// each format lays out in its own way.
+fn write_node(expr: &Expr, format: AstFormat, out: &mut String) {
+    let (kind, op, children) = match expr {
+        // Leaves are written directly
+        Expr::Number(n) => {
+            match format {
+                AstFormat::Sexpr => write!(out, "{}", n).unwrap(),
+                AstFormat::Json => write!(out, "{{\"type\":\"Number\",\"value\":{}}}", n).unwrap(),
+            }
+            return;
+        }
+        // The AST is dumped before optimization, but a folded node reads as its source
+        Expr::Folded { original, .. } => return write_node(original, format, out),
+        Expr::UnaryOp { op, operand } => ("UnaryOp", op, vec![("operand", operand)]),
+        Expr::BinaryOp { op, left, right } => ("BinaryOp", op, vec![("left", left), ("right", right)]),
+    };
+
+    match format {
+        AstFormat::Sexpr => {
+            write!(out, "({}", op).unwrap();
+            for (_, child) in children {
+                out.push(' ');
+                write_node(child, format, out);
+            }
+            out.push(')');
+        }
+        AstFormat::Json => {
+            write!(out, "{{\"type\":\"{}\",\"op\":\"{}\"", kind, op).unwrap();
+            for (name, child) in children {
+                write!(out, ",\"{}\":", name).unwrap();
+                write_node(child, format, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_source;
+
+    #[test]
+    fn sexpr_dump_of_parsed_expression() {
+        let expr = parse_source("int main() { return -(1 - 2) * 3; }");
+        assert_eq!(dump_ast(&expr, AstFormat::Sexpr), "(* (- (- 1 2)) 3)");
+    }
+
+    #[test]
+    fn json_dump_of_parsed_expression() {
+        let expr = parse_source("int main() { return -(1 - 2) * 3; }");
+        assert_eq!(
+            dump_ast(&expr, AstFormat::Json),
+            concat!(
+                r#"{"type":"BinaryOp","op":"*","left":"#,
+                r#"{"type":"UnaryOp","op":"-","operand":"#,
+                r#"{"type":"BinaryOp","op":"-","left":{"type":"Number","value":1},"right":{"type":"Number","value":2}}},"#,
+                r#""right":{"type":"Number","value":3}}"#,
+            )
+        );
+    }
+}
@@ -0,0 +1,50 @@
+// This module evaluates expression ASTs at compile time.
+// It is used wherever C requires an integer constant expression, such as enumerator values.
+//
+// Rust features used:
+// - Pattern matching for AST traversal
+// - Option for reporting expressions that have no constant value
+// - Checked and wrapping integer arithmetic
+//
+// Functionality:
+// - Computes the value of an expression tree without generating code
+// - Matches the 64-bit two's complement behaviour of the generated assembly
+use crate::ast::Expr;
+use crate::token::Token;
+
+/// Evaluates an expression to a constant integer.
+///
+/// Arithmetic wraps on overflow, just like the `add`/`sub`/`imul` instructions emitted by
+/// the code generator.
+///
+/// # Arguments
+/// * `expr` - The expression to evaluate.
+///
+/// # Returns
+/// `Some(value)` if the expression is constant, or `None` if it cannot be evaluated
+/// (for example a division by zero, which would trap at runtime).
+pub fn eval(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Number(n) => Some(*n),
+        Expr::Folded { value, .. } => Some(*value),
+        Expr::UnaryOp { op, operand } => {
+            let value = eval(operand)?;
+            match op {
+                Token::Minus => Some(value.wrapping_neg()),
+                _ => None,
+            }
+        }
+        Expr::BinaryOp { op, left, right } => {
+            let left = eval(left)?;
+            let right = eval(right)?;
+            match op {
+                Token::Plus => Some(left.wrapping_add(right)),
+                Token::Minus => Some(left.wrapping_sub(right)),
+                Token::Star => Some(left.wrapping_mul(right)),
+                // `idiv` faults on both division by zero and i64::MIN / -1.
+                Token::Slash => left.checked_div(right),
+                _ => None,
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// This module implements constant folding, the optimization enabled by `-O1`.
+// It runs as its own pass between parsing and code generation, rewriting the tree in place.
+//
+// Rust features used:
+// - The VisitorMut trait from visit.rs for the traversal
+// - std::mem::replace to move a subtree out of a &mut Expr
+//
+// Functionality:
+// - Replaces every constant sub-expression with an Expr::Folded holding its value
+// - Leaves expressions that would trap at runtime (e.g. `1 / 0`) for the generated code
+use crate::ast::Expr;
+use crate::eval::eval;
+use crate::visit::{walk_expr_mut, VisitorMut};
+
+/// Replaces the largest constant sub-expressions of `expr` with their values.
+///
+/// Literals are already as small as they get and are left alone. The replaced expression
+/// is kept in the `Folded` node so code generation can annotate the value with it.
+///
+/// # Arguments
+/// * `expr` - The expression to optimize; it is modified in place.
+pub fn fold_constants(expr: &mut Expr) {
+    ConstantFolder.visit_expr_mut(expr);
+}
+
+/// The folding pass. Visits top-down so that each constant subtree is folded as a whole.
+struct ConstantFolder;
+
+impl VisitorMut for ConstantFolder {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if matches!(expr, Expr::Number(_) | Expr::Folded { .. }) {
+            return;
+        }
+        match eval(expr) {
+            Some(value) => {
+                let original = std::mem::replace(expr, Expr::Number(value));
+                *expr = Expr::Folded { value, original: Box::new(original) };
+            }
+            // Not constant as a whole (a division that traps); fold what can be folded below
+            None => walk_expr_mut(self, expr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_source;
+    use crate::token::Token;
+
+    #[test]
+    fn constant_expression_folds_to_one_value() {
+        let mut expr = parse_source("int main() { return 2 + 3 * 4; }");
+        let original = expr.clone();
+        fold_constants(&mut expr);
+        assert_eq!(expr, Expr::Folded { value: 14, original: Box::new(original) });
+    }
+
+    #[test]
+    fn trapping_division_keeps_its_operands_and_folds_below() {
+        let mut expr = parse_source("int main() { return (1 + 1) / 0; }");
+        fold_constants(&mut expr);
+        let Expr::BinaryOp { op: Token::Slash, left, right } = expr else {
+            panic!("division should not be folded: {:?}", expr);
+        };
+        assert!(matches!(*left, Expr::Folded { value: 2, .. }));
+        assert_eq!(*right, Expr::Number(0));
+    }
+}
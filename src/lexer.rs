@@ -1,5 +1,62 @@
-// Import the Token enum, which defines all possible token types.
-use crate::token::Token;
+// Import the Token enum, which defines all possible token types, and the Span type
+// used to record where each token appears in the source.
+use crate::token::{IntSuffix, Span, Token};
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Width of a tab stop used when computing display columns.
+pub const TAB_WIDTH: usize = 8;
+
+/// Character iterator that keeps track of the current line and column.
+///
+/// Two columns are tracked: the raw column, where every character (including a tab)
+/// counts as one, and the display column, where a tab advances to the next tab stop.
+/// The display column is what a terminal renderer needs to place a caret correctly.
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    display_column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Cursor { chars: input.chars().peekable(), line: 1, column: 1, display_column: 1 }
+    }
+
+    /// Returns the next character without consuming it.
+    fn peek(&mut self) -> Option<&char> {
+        self.chars.peek()
+    }
+
+    /// Consumes the next character, advancing the line and column counters.
+    fn next(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        match ch {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+                self.display_column = 1;
+            }
+            '\t' => {
+                self.column += 1;
+                // Advance to the next tab stop (columns are 1-based).
+                self.display_column += TAB_WIDTH - (self.display_column - 1) % TAB_WIDTH;
+            }
+            _ => {
+                self.column += 1;
+                self.display_column += 1;
+            }
+        }
+        Some(ch)
+    }
+
+    /// Returns a span starting at the current position with zero length.
+    fn span(&self) -> Span {
+        Span { line: self.line, column: self.column, display_column: self.display_column, len: 0 }
+    }
+}
 
 /// Tokenizes the input source string into a vector of tokens.
 ///
@@ -12,17 +69,35 @@ use crate::token::Token;
 /// # Returns
 /// * `Vec<Token>` - A vector containing the tokens found in the input.
 pub fn tokenize(input: &str) -> Vec<Token> {
-    // Create a peekable iterator over the input characters.
-    let mut chars = input.chars().peekable();
+    tokenize_with_spans(input).into_iter().map(|(token, _)| token).collect()
+}
+
+/// Tokenizes the input source string, pairing each token with its source span.
+///
+/// # Arguments
+/// * `input` - The source code as a string slice.
+///
+/// # Returns
+/// * `Vec<(Token, Span)>` - The tokens found in the input along with their positions.
+pub fn tokenize_with_spans(input: &str) -> Vec<(Token, Span)> {
+    // Skip a UTF-8 byte-order mark left by some editors. It is removed before scanning,
+    // so it does not count towards the column of the first token.
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    // Create a position-tracking iterator over the input characters.
+    let mut chars = Cursor::new(input);
     // Vector to store the resulting tokens.
     let mut tokens = Vec::new();
 
     // Main loop: process each character until the end of input.
     while let Some(&ch) = chars.peek() {
-        match ch {
+        // Remember where this token starts.
+        let mut span = chars.span();
+
+        let token = match ch {
             // Skip whitespace characters (space, newline, tab).
             ' ' | '\n' | '\t' => {
                 chars.next();
+                continue;
             }
 
             // Parse numeric literals (integers).
@@ -33,8 +108,30 @@ pub fn tokenize(input: &str) -> Vec<Token> {
                     num.push(c);
                     chars.next();
                 }
+                // Collect a type suffix such as U, L or UL. Any letters directly after the
+                // digits belong to the literal, so `5abc` is an invalid suffix, not `5` `abc`.
+                let mut suffix_text = String::new();
+                while let Some(&c @ ('a'..='z' | 'A'..='Z' | '_' | '0'..='9')) = chars.peek() {
+                    suffix_text.push(c);
+                    chars.next();
+                }
+                let suffix = IntSuffix::parse(&suffix_text).unwrap_or_else(|| {
+                    panic!("Invalid suffix '{}' on integer literal at {}", suffix_text, span)
+                });
                 // Convert the string to an integer and create a Number token.
-                tokens.push(Token::Number(num.parse().unwrap()));
+                // Literals are read as unsigned so that 9223372036854775808 (the magnitude of
+                // i64::MIN) can be lexed; it wraps to i64::MIN, and the parser only accepts it
+                // directly after a unary minus.
+                // The suffix is only a type hint for now: values are signed 64-bit, so an
+                // unsigned literal above i64::MAX is rejected rather than wrapped.
+                let value: u64 = num
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Integer literal too large: {} at {}", num, span));
+                let max = if suffix.unsigned { i64::MAX.unsigned_abs() } else { i64::MIN.unsigned_abs() };
+                if value > max {
+                    panic!("Integer literal too large: {} at {}", num, span);
+                }
+                Token::Number(value as i64, suffix)
             }
 
             // Parse identifiers and keywords.
@@ -47,27 +144,111 @@ pub fn tokenize(input: &str) -> Vec<Token> {
                 }
                 // Check for reserved keywords; otherwise, treat as identifier.
                 match ident.as_str() {
-                    "int" => tokens.push(Token::Int),
-                    "return" => tokens.push(Token::Return),
-                    _ => tokens.push(Token::Ident(ident)),
+                    "int" => Token::Int,
+                    "void" => Token::Void,
+                    "char" => Token::Char,
+                    "return" => Token::Return,
+                    "register" => Token::Register,
+                    "auto" => Token::Auto,
+                    "enum" => Token::Enum,
+                    "sizeof" => Token::Sizeof,
+                    _ => Token::Ident(ident),
                 }
             }
 
             // Single-character tokens for operators and punctuation.
-            '+' => { tokens.push(Token::Plus); chars.next(); } // Plus operator
-            '-' => { tokens.push(Token::Minus); chars.next(); } // Minus operator
-            '*' => { tokens.push(Token::Star); chars.next(); } // Multiplication operator
-            '/' => { tokens.push(Token::Slash); chars.next(); } // Division operator
-            '(' => { tokens.push(Token::LParen); chars.next(); } // Left parenthesis
-            ')' => { tokens.push(Token::RParen); chars.next(); } // Right parenthesis
-            '{' => { tokens.push(Token::LBrace); chars.next(); } // Left brace
-            '}' => { tokens.push(Token::RBrace); chars.next(); } // Right brace
-            ';' => { tokens.push(Token::Semicolon); chars.next(); } // Semicolon
+            '+' => { chars.next(); Token::Plus } // Plus operator
+            '-' => { chars.next(); Token::Minus } // Minus operator
+            '*' => { chars.next(); Token::Star } // Multiplication operator
+            '/' => { chars.next(); Token::Slash } // Division operator
+            '(' => { chars.next(); Token::LParen } // Left parenthesis
+            ')' => { chars.next(); Token::RParen } // Right parenthesis
+            '{' => { chars.next(); Token::LBrace } // Left brace
+            '}' => { chars.next(); Token::RBrace } // Right brace
+            ';' => { chars.next(); Token::Semicolon } // Semicolon
+            ',' => { chars.next(); Token::Comma } // Comma
+            '=' => { chars.next(); Token::Assign } // Assignment
 
             // Any other character is unexpected and causes a panic.
-            _ => panic!("Unexpected character: {}", ch),
-        }
+            _ => panic!("Unexpected character: {} at {}", ch, span),
+        };
+
+        // Tokens never span lines, so the length is the distance travelled on this line.
+        span.len = chars.column - span.column;
+        tokens.push((token, span));
     }
     // Return the vector of tokens.
     tokens
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_after_tab_reports_raw_and_display_columns() {
+        let tokens = tokenize_with_spans("int\tmain\n  \t x");
+        // `main` follows a tab at column 4: one raw column, but the tab expands to column 9
+        let (token, span) = &tokens[1];
+        assert_eq!(*token, Token::Ident("main".into()));
+        assert_eq!((span.line, span.column, span.display_column, span.len), (1, 5, 9, 4));
+        // Two spaces then a tab reach the first tab stop; one more space follows
+        let (_, span) = &tokens[2];
+        assert_eq!((span.line, span.column, span.display_column), (2, 5, 10));
+    }
+
+    #[test]
+    fn leading_byte_order_mark_is_skipped() {
+        let source = "int main() { return 0; }";
+        let with_bom = tokenize_with_spans(&format!("\u{feff}{}", source));
+        assert_eq!(with_bom, tokenize_with_spans(source));
+        assert_eq!(with_bom[0].1.column, 1);
+        assert_eq!(crate::compile(&format!("\u{feff}{}", source)).unwrap(), crate::compile(source).unwrap());
+    }
+
+    #[test]
+    fn integer_suffixes_are_recorded_on_the_literal() {
+        let suffix = |unsigned, long| IntSuffix { unsigned, long };
+        assert_eq!(
+            tokenize("10L 5U 3UL 7lu"),
+            [
+                Token::Number(10, suffix(false, true)),
+                Token::Number(5, suffix(true, false)),
+                Token::Number(3, suffix(true, true)),
+                Token::Number(7, suffix(true, true)),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid suffix 'LUL' on integer literal at 1:1")]
+    fn repeated_integer_suffixes_are_rejected() {
+        tokenize("5LUL");
+    }
+
+    #[test]
+    fn storage_class_keywords_are_reserved() {
+        assert_eq!(
+            tokenize("register auto registers"),
+            [Token::Register, Token::Auto, Token::Ident("registers".into())]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected RBrace, got Some(Auto)")]
+    fn storage_class_keywords_are_not_enumerator_names() {
+        crate::parser::parse_source("enum { auto }; int main() { return 0; }");
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer literal too large: 18446744073709551615 at 1:1")]
+    fn unsigned_literals_above_i64_max_are_rejected() {
+        tokenize("18446744073709551615U");
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer literal too large: 9223372036854775808 at 1:2")]
+    fn unsigned_magnitude_of_i64_min_is_rejected() {
+        tokenize("-9223372036854775808U");
+    }
+}
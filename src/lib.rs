@@ -1,5 +1,91 @@
 pub mod token;
 pub mod lexer;
 pub mod ast;
+pub mod visit;
 pub mod parser;
-pub mod codegen;
\ No newline at end of file
+pub mod codegen;
+pub mod eval;
+pub mod fold;
+pub mod dump;
+pub mod backend;
+pub mod repl;
+
+use std::fmt;
+use std::panic;
+
+/// An error reported while compiling a program.
+///
+/// The pipeline reports errors by panicking; `compile` catches the panic at the library
+/// boundary and keeps its message. This relies on unwinding: the panic hook still runs
+/// first (the default hook prints the message to stderr), and a program built with
+/// `panic = "abort"` exits instead of receiving the error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileError {
+    /// The panic message, e.g. "Use of undeclared identifier 'x' at 1:21".
+    pub message: String,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// Compiles C source code to x86_64 assembly with the default options.
+///
+/// # Arguments
+/// * `source` - The C source code.
+///
+/// # Returns
+/// The generated NASM assembly, or the error that stopped compilation
+/// (see `CompileError` for how errors are caught).
+pub fn compile(source: &str) -> Result<String, CompileError> {
+    panic::catch_unwind(|| {
+        let mut parser = parser::Parser::with_spans(lexer::tokenize_with_spans(source));
+        codegen::generate_asm(&parser.parse())
+    })
+    .map_err(|payload| {
+        // panic! with a format string carries a String; a plain literal carries a &str
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "unknown error".to_string(),
+            },
+        };
+        CompileError { message }
+    })
+}
+
+/// Compiles C source code to x86_64 assembly, returned as UTF-8 bytes.
+///
+/// Convenient for callers writing to byte sinks; the bytes are identical to `compile`'s output.
+///
+/// # Arguments
+/// * `source` - The C source code.
+///
+/// # Returns
+/// The generated NASM assembly as bytes, or the error that stopped compilation.
+pub fn compile_to_vec(source: &str) -> Result<Vec<u8>, CompileError> {
+    compile(source).map(String::into_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compile_to_vec_matches_compile() {
+        let source = "int main() { return 2 * (3 + 4); }";
+        assert_eq!(compile_to_vec(source), Ok(compile(source).unwrap().into_bytes()));
+    }
+
+    #[test]
+    fn compile_returns_errors() {
+        let error = compile("int main() { return x; }").unwrap_err();
+        assert_eq!(error.message, "Use of undeclared identifier 'x' at 1:21");
+        assert_eq!(compile_to_vec("int main() { return x; }"), Err(error));
+    }
+}
@@ -3,53 +3,219 @@
 // 1. Reads the input C file
 // 2. Tokenizes the source
 // 3. Parses tokens into an AST
-// 4. Generates x86_64 assembly from the AST
-// 5. Writes the assembly to output.asm
-mod lexer;
-mod parser;
-mod ast;
-mod codegen;
-mod token;
-
+// 4. Folds constants (-O1)
+// 5. Generates x86_64 assembly (or another backend's output) from the AST
+// 6. Writes the assembly to output.asm
+//
+// The compiler itself lives in the library crate (src/lib.rs); this binary is a thin driver.
 use std::env; // For reading command-line arguments
 use std::fs;  // For file I/O
+use std::io::Read; // For reading an expression from stdin
+use std::path::Path; // For deriving output file names
+use std::process::Command; // For invoking the assembler
+use std::time::{Duration, Instant}; // For --time-passes
 
-use lexer::tokenize;         // Tokenizer for C source
-use parser::Parser;          // Parser for tokens to AST
-use codegen::generate_asm;   // Code generator for assembly
+use min_cc::lexer::tokenize_with_spans; // Tokenizer for C source
+use min_cc::parser::Parser;          // Parser for tokens to AST
+use min_cc::backend::{AstBackend, Backend, X86Backend}; // Output generators
+use min_cc::codegen::{CodegenOptions, Syntax, Target}; // Options for the assembly backend
+use min_cc::dump::AstFormat;         // AST rendering for --emit ast
+use min_cc::fold::fold_constants;    // Constant folding for -O1
+use min_cc::repl;                    // Interactive expression evaluator
 
 /// Main function: orchestrates the compilation pipeline.
 ///
 /// Steps:
-/// 1. Checks for correct usage (expects one argument: input file)
+/// 1. Checks for correct usage (expects one input file, optionally preceded by flags;
+///    with --expr the input may come from stdin instead)
 /// 2. Reads the input C file
 /// 3. Tokenizes the input
 /// 4. Parses tokens into an AST
-/// 5. Generates assembly code from the AST
-/// 6. Writes the assembly to output.asm
+/// 5. With -O1, folds constant sub-expressions
+/// 6. Generates assembly code from the AST
+/// 7. Writes the assembly to output.asm (or the -o path)
+/// 8. With -c, assembles it into an object file using nasm (or as for GAS syntax)
 fn main() {
-    // Collect command-line arguments
-    let args: Vec<String> = env::args().collect();
+    // Collect command-line arguments: flags configure codegen, the rest is the input file
+    let mut options = CodegenOptions::default();
+    let mut path = None;
+    let mut interactive = false;
+    let mut emit_ast = false;
+    let mut ast_format = AstFormat::Sexpr;
+    let mut assemble = false;
+    let mut output_path = None;
+    let mut time_passes = false;
+    let mut expr_only = false;
+    let mut optimize = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-start" => options.emit_start = false, // Omit the _start stub
+            "--repl" => interactive = true,              // Evaluate expressions interactively
+            "-O0" => optimize = false,                   // No optimization (default)
+            "-O1" => optimize = true,                    // Fold constant expressions
+            "--fold-comments" => options.fold_comments = true, // Annotate folded values
+            // Choose what to output: assembly (default) or the parsed AST
+            "--emit" => match args.next().as_deref() {
+                Some("asm") => emit_ast = false,
+                Some("ast") => emit_ast = true,
+                _ => usage(),
+            },
+            // Format used by --emit ast
+            "--ast-format" => {
+                ast_format = args
+                    .next()
+                    .as_deref()
+                    .and_then(AstFormat::from_name)
+                    .unwrap_or_else(|| usage());
+            }
+            // Operating system ABI to target
+            "--target" => {
+                options.target = args
+                    .next()
+                    .as_deref()
+                    .and_then(Target::from_triple)
+                    .unwrap_or_else(|| usage());
+            }
+            // Assembler dialect to write
+            "--syntax" => {
+                options.syntax = args
+                    .next()
+                    .as_deref()
+                    .and_then(Syntax::from_name)
+                    .unwrap_or_else(|| usage());
+            }
+            "--time-passes" => time_passes = true,       // Report time spent in each phase
+            "--expr" => expr_only = true,                // Input is a bare expression
+            "-c" => assemble = true,                     // Assemble to an object file, don't link
+            "-o" => output_path = Some(args.next().unwrap_or_else(|| usage())), // Output file name
+            _ if arg.starts_with('-') => usage(),
+            _ if path.is_none() => path = Some(arg),
+            _ => usage(),
+        }
+    }
 
-    // Ensure the user provided exactly one input file
-    if args.len() != 2 {
-        eprintln!("Usage: c_compiler <file.c>");
-        std::process::exit(1);
+    // The REPL reads from stdin and needs no input file
+    if interactive {
+        // Report parse errors as one-line messages instead of full panic output
+        std::panic::set_hook(Box::new(|info| {
+            eprintln!("error: {}", info.payload_as_str().unwrap_or("unknown error"));
+        }));
+        let stdin = std::io::stdin();
+        repl::run(stdin.lock(), std::io::stdout()).expect("Failed to run REPL");
+        return;
     }
 
-    // Read the input C source file
-    let input = fs::read_to_string(&args[1])
-        .expect("Failed to read input file");
+    // Read the input C source file. A bare expression (--expr) may also come from stdin.
+    let input = match path {
+        Some(path) => fs::read_to_string(path).expect("Failed to read input file"),
+        None if expr_only => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input).expect("Failed to read stdin");
+            input
+        }
+        // Ensure the user provided exactly one input file
+        None => usage(),
+    };
+
+    // Wall-clock time of each phase, for --time-passes
+    let mut timings: Vec<(&str, Duration)> = Vec::new();
 
     // Tokenize the input source code
-    let tokens = tokenize(&input);
-    // Parse tokens into an AST
-    let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    // Generate x86_64 assembly from the AST
-    let asm = generate_asm(&ast);
-
-    // Write the generated assembly to output.asm
-    fs::write("output.asm", asm).expect("Failed to write output.asm");
-    println!("Assembly written to output.asm");
+    let start = Instant::now();
+    let tokens = tokenize_with_spans(&input);
+    timings.push(("lexing", start.elapsed()));
+    // Parse tokens into an AST, keeping spans for error messages
+    let start = Instant::now();
+    let mut parser = Parser::with_spans(tokens);
+    // With --expr the input is just the value main returns
+    let mut ast = if expr_only { parser.parse_expression() } else { parser.parse() };
+    timings.push(("parsing", start.elapsed()));
+    // Optimize the tree; the AST dump shows the program as written
+    if optimize && !emit_ast {
+        let start = Instant::now();
+        fold_constants(&mut ast);
+        timings.push(("optimization", start.elapsed()));
+    }
+
+    // Pick the backend: x86_64 assembly by default, or an AST dump
+    let object_format = options.target.nasm_format();
+    let syntax = options.syntax;
+    let backend: Box<dyn Backend> = if emit_ast {
+        Box::new(AstBackend { format: ast_format })
+    } else {
+        Box::new(X86Backend { options })
+    };
+    let start = Instant::now();
+    let output = backend.emit_program(&ast);
+    timings.push(("codegen", start.elapsed()));
+    if time_passes {
+        print_timings(&timings);
+    }
+
+    // The AST dump goes to stdout
+    if emit_ast {
+        println!("{}", output);
+        return;
+    }
+
+    // With -c, -o names the object file and the assembly is written next to it;
+    // otherwise -o names the assembly file
+    let (asm_path, object_path) = if assemble {
+        let object_path = output_path.unwrap_or_else(|| "output.o".to_string());
+        let asm_path = Path::new(&object_path)
+            .with_extension(syntax.extension())
+            .to_string_lossy()
+            .into_owned();
+        // `-o x.asm` (or `-o x.s` for GAS) would make the assembler overwrite its own input
+        if asm_path == object_path {
+            eprintln!("-o {} is the assembly file; name the object file, e.g. -o output.o", object_path);
+            std::process::exit(1);
+        }
+        (asm_path, Some(object_path))
+    } else {
+        (output_path.unwrap_or_else(|| format!("output.{}", syntax.extension())), None)
+    };
+
+    // Write the generated assembly
+    fs::write(&asm_path, output).unwrap_or_else(|_| panic!("Failed to write {}", asm_path));
+    println!("Assembly written to {}", asm_path);
+
+    // Assemble into an object file with the assembler matching the syntax;
+    // linking is left to the user
+    if let Some(object_path) = object_path {
+        let mut command = match syntax {
+            Syntax::Nasm => {
+                let mut command = Command::new("nasm");
+                command.args(["-f", object_format]);
+                command
+            }
+            Syntax::GasIntel => Command::new("as"),
+        };
+        let assembler = command.get_program().to_string_lossy().into_owned();
+        let status = command
+            .args([&asm_path, "-o", &object_path])
+            .status()
+            .unwrap_or_else(|_| panic!("Failed to run {} (is it installed?)", assembler));
+        if !status.success() {
+            eprintln!("{} failed to assemble {}", assembler, asm_path);
+            std::process::exit(1);
+        }
+        println!("Object file written to {}", object_path);
+    }
+}
+
+/// Prints the time spent in each phase, and the total, as a table on stderr.
+fn print_timings(timings: &[(&str, Duration)]) {
+    let total: Duration = timings.iter().map(|(_, time)| *time).sum();
+    eprintln!("{:<12} {:>12}", "phase", "time (ms)");
+    for (phase, time) in timings.iter().chain([&("total", total)]) {
+        eprintln!("{:<12} {:>12.3}", phase, time.as_secs_f64() * 1000.0);
+    }
+}
+
+/// Prints the usage message and exits with an error status.
+fn usage() -> ! {
+    eprintln!("Usage: c_compiler [--no-start] [-O0|-O1] [--fold-comments] [--target <triple>] [--syntax nasm|gas-intel] [--time-passes] [--expr] [--emit asm|ast] [--ast-format sexpr|json] [-c] [-o <file>] <file.c>\n       c_compiler --repl");
+    std::process::exit(1);
 }
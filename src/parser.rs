@@ -7,25 +7,52 @@
 //
 // Functionality:
 // - Converts a vector of tokens into an AST
-// - Handles operator precedence and associativity for +, -, *, /
+// - Handles operator precedence and associativity for +, -, *, / and unary -
 // - Expects a minimal C program structure: int main() { return <expr>; }
-use crate::token::Token;
-use crate::ast::Expr;
+// - Checks that main has a signature the _start stub can call
+// - Records enum constants and folds references to them into integer literals
+use std::collections::HashMap;
+
+use crate::token::{Span, Token};
+use crate::ast::{Expr, INT_SIZE};
+use crate::eval::eval;
 
 /// Parser that takes a list of tokens and produces an AST.
+///
+/// A parser is single-use: `parse` starts from the current position, so calling it a
+/// second time continues after the tokens already consumed. If parsing panics on an
+/// unexpected token, the position is left at that token and `remaining` returns it
+/// together with everything after it.
 /// 
 /// Fields:
 /// - tokens: Vector of tokens to parse
+/// - spans: Source positions of the tokens, if known (used in error messages)
 /// - pos: Current position in the token stream
+/// - constants: Values of the enumerators declared so far
 pub struct Parser {
     tokens: Vec<Token>,
+    spans: Vec<Span>,
     pos: usize,
+    constants: HashMap<String, i64>,
 }
 
 impl Parser {
     /// Creates a new parser with the given tokens.
     pub fn new(tokens: Vec<Token>) -> Self {
-        Parser {tokens, pos: 0}
+        Parser {tokens, spans: Vec::new(), pos: 0, constants: HashMap::new()}
+    }
+
+    /// Creates a new parser from tokens paired with their source spans,
+    /// so that errors can report where in the source they occurred.
+    pub fn with_spans(tokens: Vec<(Token, Span)>) -> Self {
+        let (tokens, spans) = tokens.into_iter().unzip();
+        Parser {tokens, spans, pos: 0, constants: HashMap::new()}
+    }
+
+    /// Returns the tokens that have not been consumed yet.
+    /// Useful for tooling that wants to inspect where parsing stopped.
+    pub fn remaining(&self) -> &[Token] {
+        &self.tokens[self.pos..]
     }
 
     /// Returns the current token, or None if at end of input.
@@ -43,20 +70,170 @@ impl Parser {
         }
     }
 
+    /// Consumes the current token if it matches the expected token.
+    /// Returns whether it was consumed.
+    fn accept(&mut self, expected: &Token) -> bool {
+        if self.current() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes the `;` that terminates a statement or declaration, otherwise panics.
+    /// The error points just past the previous token, where the semicolon is missing,
+    /// rather than at whatever token happens to follow.
+    fn eat_semicolon(&mut self, after: &str) {
+        if self.current() == Some(&Token::Semicolon) {
+            self.pos += 1;
+            return;
+        }
+        let end = self.pos.checked_sub(1).and_then(|prev| self.spans.get(prev)).map(|span| Span {
+            column: span.column + span.len,
+            display_column: span.display_column + span.len,
+            len: 0,
+            ..*span
+        });
+        match end {
+            Some(span) => panic!("Expected ';' after {} at {}", after, span),
+            None => panic!("Expected ';' after {}", after),
+        }
+    }
+
     /// Parses a full minimal C program of the form: int main() { return <expr>; }
+    /// Enum declarations may appear at the top level and at the start of main's body.
+    /// Stray semicolons at the top level (e.g. `int main(){...};`) are ignored.
     /// Returns the parsed expression AST.
     pub fn parse(&mut self) -> Expr {
+        self.parse_top_level(); // declarations before main
         // Expect the sequence of tokens for a minimal main function
+        self.parse_main_signature(); // 'int main(...)'
+        self.eat(&Token::LBrace); // '{'
+        self.parse_enums(); // enum declarations inside main
+        self.eat(&Token::Return); // 'return'
+        let expr = self.parse_expr(); // Parse the arithmetic expression
+        self.eat_semicolon("return statement"); // ';'
+        self.eat(&Token::RBrace); // '}'
+        self.parse_top_level(); // declarations after main
+
+        // Nothing else may follow main
+        if let Some(token) = self.current() {
+            panic!("Unexpected token after main: `{}`", token);
+        }
+        expr
+    }
+
+    /// Parses the declarator of main and checks its signature.
+    /// The _start stub calls main and exits with the value in rax, so main must return
+    /// int and take either no parameters or (int argc, char **argv); names are optional.
+    fn parse_main_signature(&mut self) {
+        const EXPECTED: &str = "`int main(void)`, `int main()` or `int main(int argc, char **argv)`";
+
+        // Return type: anything but int (including unsupported types such as `long`,
+        // which lex as identifiers) is rejected with a signature error
+        if let Some(ty) = self.current()
+            && *ty != Token::Int
+        {
+            panic!("Invalid return type `{}` for main: expected {}", ty, EXPECTED);
+        }
         self.eat(&Token::Int); // 'int'
         self.eat(&Token::Ident("main".into())); // 'main'
         self.eat(&Token::LParen); // '('
+
+        // Parameter list: (), (void) or (int [argc], char **[argv])
+        let valid = if self.accept(&Token::Int) {
+            self.skip_ident();
+            let valid = self.accept(&Token::Comma)
+                && self.accept(&Token::Char)
+                && self.accept(&Token::Star)
+                && self.accept(&Token::Star);
+            self.skip_ident();
+            valid
+        } else {
+            self.accept(&Token::Void);
+            true
+        };
+        if !valid || self.current() != Some(&Token::RParen) {
+            panic!("Invalid parameters for main: expected {}", EXPECTED);
+        }
         self.eat(&Token::RParen); // ')'
+    }
+
+    /// Skips an optional identifier, such as a parameter name.
+    fn skip_ident(&mut self) {
+        if let Some(Token::Ident(_)) = self.current() {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses input consisting of a single expression, e.g. `1 + 2 * 3`, as the value
+    /// returned by main. Used to compile expressions without the main boilerplate.
+    pub fn parse_expression(&mut self) -> Expr {
+        let expr = self.parse_expr();
+        // The expression must make up the whole input
+        if let Some(token) = self.current() {
+            panic!("Unexpected token after expression: `{}`", token);
+        }
+        expr
+    }
+
+    /// Parses top-level enum declarations and skips empty declarations (lone semicolons).
+    fn parse_top_level(&mut self) {
+        loop {
+            match self.current() {
+                Some(Token::Enum) => self.parse_enum(),
+                Some(Token::Semicolon) => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    /// Parses any number of consecutive enum declarations.
+    fn parse_enums(&mut self) {
+        while self.current() == Some(&Token::Enum) {
+            self.parse_enum();
+        }
+    }
+
+    /// Parses an enum declaration: enum [Name] { A, B = <expr>, ... };
+    /// Each enumerator is recorded as a named constant. Enumerators without an explicit
+    /// value take the previous value plus one, starting from zero.
+    fn parse_enum(&mut self) {
+        self.eat(&Token::Enum); // 'enum'
+        // The optional tag name is accepted but not recorded
+        if let Some(Token::Ident(_)) = self.current() {
+            self.pos += 1;
+        }
         self.eat(&Token::LBrace); // '{'
-        self.eat(&Token::Return); // 'return'
-        let expr = self.parse_expr(); // Parse the arithmetic expression
-        self.eat(&Token::Semicolon); // ';'
+
+        let mut value = 0;
+        while let Some(Token::Ident(name)) = self.current() {
+            let name = name.clone();
+            self.pos += 1;
+            // An explicit value must be an integer constant expression
+            if self.current() == Some(&Token::Assign) {
+                self.pos += 1;
+                let expr = self.parse_expr();
+                value = eval(&expr)
+                    .unwrap_or_else(|| panic!("Enumerator {} is not an integer constant", name));
+            }
+            if self.constants.contains_key(&name) {
+                panic!("Duplicate enumerator: {}", name);
+            }
+            self.constants.insert(name, value);
+            value = value.wrapping_add(1);
+
+            // Enumerators are separated by commas; a trailing comma is allowed
+            if self.current() == Some(&Token::Comma) {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+
         self.eat(&Token::RBrace); // '}'
-        expr
+        self.eat_semicolon("enum declaration"); // ';'
     }
 
     /// Parses an expression, starting with addition/subtraction.
@@ -92,14 +269,14 @@ impl Parser {
     /// Parses multiplication and division, left-associative.
     /// Handles chains of * and / operators, respecting precedence.
     fn parse_mul_div(&mut self) -> Expr {
-        let mut node = self.parse_primary();
+        let mut node = self.parse_unary();
 
         while let Some(token) = self.current() {
             match token {
                 Token::Star | Token::Slash => {
                     let op = token.clone();
                     self.pos += 1;
-                    let right = self.parse_primary();
+                    let right = self.parse_unary();
                     node = Expr::BinaryOp {
                         op,
                         left: Box::new(node),
@@ -113,15 +290,74 @@ impl Parser {
         node
     }
 
-    /// Parses a primary expression: number or parenthesized expression.
-    /// Handles integer literals and expressions in parentheses.
+    /// Parses a unary expression: a primary expression preceded by any number of unary
+    /// minuses or `sizeof` operators.
+    /// Negating a literal is folded into a negative literal so codegen emits a single mov.
+    fn parse_unary(&mut self) -> Expr {
+        match self.current() {
+            Some(Token::Sizeof) => {
+                self.pos += 1;
+                // sizeof(int) names the type directly
+                if self.current() == Some(&Token::LParen) && self.tokens.get(self.pos + 1) == Some(&Token::Int) {
+                    self.eat(&Token::LParen);
+                    self.eat(&Token::Int);
+                    self.eat(&Token::RParen);
+                } else {
+                    // sizeof <unary-expr>: the operand is never evaluated, and every
+                    // expression has type int, so only its size matters
+                    self.parse_unary();
+                }
+                Expr::Number(INT_SIZE)
+            }
+            Some(Token::Minus) => {
+                self.pos += 1;
+                // -9223372036854775808 is i64::MIN: the lexer stores the literal's magnitude,
+                // 2^63, wrapped to i64::MIN, which only makes sense directly after a minus
+                if let Some(Token::Number(i64::MIN, _)) = self.current() {
+                    self.pos += 1;
+                    return Expr::Number(i64::MIN);
+                }
+                match self.parse_unary() {
+                    // Wrapping keeps -(-9223372036854775808) at i64::MIN, as `neg` would
+                    Expr::Number(n) => Expr::Number(n.wrapping_neg()),
+                    operand => Expr::UnaryOp {
+                        op: Token::Minus,
+                        operand: Box::new(operand),
+                    },
+                }
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    /// Parses a primary expression: number, enum constant or parenthesized expression.
+    /// Handles integer literals, enumerator names (folded to their value) and expressions in parentheses.
     fn parse_primary(&mut self) -> Expr {
         match self.current() {
-            Some(Token::Number(n)) => {
+            // A literal of magnitude 2^63 is only valid as the operand of unary minus
+            Some(Token::Number(i64::MIN, _)) => {
+                match self.spans.get(self.pos) {
+                    Some(span) => panic!("Integer literal too large: 9223372036854775808 at {}", span),
+                    None => panic!("Integer literal too large: 9223372036854775808"),
+                }
+            }
+            // The suffix only carries a type hint; every value is 64 bits wide
+            Some(Token::Number(n, _)) => {
                 let value = *n;
                 self.pos += 1;
                 Expr::Number(value)
             }
+            Some(Token::Ident(name)) => {
+                // Only names declared earlier in the source are visible
+                let Some(&value) = self.constants.get(name) else {
+                    match self.spans.get(self.pos) {
+                        Some(span) => panic!("Use of undeclared identifier '{}' at {}", name, span),
+                        None => panic!("Use of undeclared identifier '{}'", name),
+                    }
+                };
+                self.pos += 1;
+                Expr::Number(value)
+            }
             Some(Token::LParen) => {
                 self.pos += 1;
                 let expr = self.parse_expr();
@@ -133,3 +369,151 @@ impl Parser {
     }
 }
 
+/// Parses a full program and returns the expression main returns.
+/// Shared by the test modules of the other passes.
+#[cfg(test)]
+pub(crate) fn parse_source(source: &str) -> Expr {
+    Parser::with_spans(crate::lexer::tokenize_with_spans(source)).parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negated_literals_fold_to_negative_numbers() {
+        assert_eq!(parse_source("int main() { return -5; }"), Expr::Number(-5));
+        assert_eq!(parse_source("int main() { return -2147483648; }"), Expr::Number(-2147483648));
+        assert_eq!(parse_source("int main() { return -9223372036854775808; }"), Expr::Number(i64::MIN));
+        assert_eq!(parse_source("int main() { return - -9223372036854775808; }"), Expr::Number(i64::MIN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer literal too large: 9223372036854775808 at 1:21")]
+    fn magnitude_of_i64_min_needs_a_minus() {
+        parse_source("int main() { return 9223372036854775808; }");
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer literal too large")]
+    fn magnitude_of_i64_min_in_parentheses_is_too_large() {
+        parse_source("int main() { return -(9223372036854775808); }");
+    }
+
+    #[test]
+    fn sizeof_yields_the_int_size() {
+        let source = "enum { x }; int main() { return sizeof x; }";
+        assert_eq!(parse_source(source), Expr::Number(INT_SIZE));
+        let source = "enum { x }; int main() { return sizeof (x + 1); }";
+        assert_eq!(parse_source(source), Expr::Number(INT_SIZE));
+        assert_eq!(parse_source("int main() { return sizeof(int); }"), Expr::Number(INT_SIZE));
+    }
+
+    #[test]
+    fn sizeof_binds_like_a_unary_operator() {
+        // (sizeof 3) + 1, not sizeof (3 + 1)
+        assert_eq!(eval(&parse_source("int main() { return sizeof 3 + 1; }")), Some(9));
+        assert_eq!(eval(&parse_source("int main() { return 2 * sizeof -1; }")), Some(16));
+    }
+
+    #[test]
+    fn remaining_tokens_start_at_the_parse_error() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut parser = Parser::new(crate::lexer::tokenize("int main() { return 1 + ; }"));
+        // Errors are panics, so a caller catches the unwind and then inspects the parser
+        let result = catch_unwind(AssertUnwindSafe(|| parser.parse()));
+        assert!(result.is_err());
+        assert_eq!(parser.remaining(), [Token::Semicolon, Token::RBrace]);
+    }
+
+    #[test]
+    fn enumerators_count_up_from_zero() {
+        assert_eq!(parse_source("int main() { enum { A, B, C }; return B; }"), Expr::Number(1));
+    }
+
+    #[test]
+    fn enumerators_continue_from_explicit_values() {
+        let source = "enum Color { RED = 1, GREEN, BLUE }; int main() { return RED * 100 + GREEN * 10 + BLUE; }";
+        assert_eq!(eval(&parse_source(source)), Some(123));
+        // Explicit values may refer to earlier enumerators
+        let source = "enum { X = 4, Y = X * 2, Z, }; int main() { return Z; }";
+        assert_eq!(parse_source(source), Expr::Number(9));
+    }
+
+    #[test]
+    #[should_panic(expected = "Duplicate enumerator: A")]
+    fn duplicate_enumerators_are_rejected() {
+        parse_source("enum { A, B }; int main() { enum { A }; return A; }");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected ';' after return statement at 1:22")]
+    fn missing_semicolon_is_reported_after_the_previous_token() {
+        // `1` is at column 21, so the ';' belongs at column 22 rather than at the next `return`
+        parse_source("int main() { return 1 return 2; }");
+    }
+
+    #[test]
+    fn trailing_semicolons_after_main_are_ignored() {
+        assert_eq!(parse_source("int main() { return 3; };"), Expr::Number(3));
+        assert_eq!(parse_source("int main() { return 3; } ;; ;"), Expr::Number(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Use of undeclared identifier 'x' at 2:12")]
+    fn undeclared_identifier_is_reported_with_its_position() {
+        parse_source("int main() {\n    return x;\n}");
+    }
+
+    #[test]
+    fn main_may_take_void() {
+        assert_eq!(parse_source("int main(void) { return 0; }"), Expr::Number(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid return type `void` for main: expected `int main(void)`")]
+    fn void_main_is_rejected() {
+        parse_source("void main() {}");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid return type `long` for main: expected `int main(void)`")]
+    fn unknown_return_type_is_rejected() {
+        parse_source("long main() { return 0; }");
+    }
+
+    #[test]
+    fn parenthesized_unary_on_the_right_of_each_operator() {
+        let cases = [
+            ("12 + (-3)", 9),
+            ("12 - (-3)", 15),
+            ("12 * (-3)", -36),
+            ("12 / (-3)", -4),
+            ("12 + -(1 + 2)", 9),
+            ("12 - -(1 + 2)", 15),
+            ("12 * (-(1 + 2))", -36),
+            ("12 / -(1 + 2)", -4),
+            ("12 + (sizeof 1)", 20),
+            ("12 - (sizeof(int))", 4),
+            ("12 * sizeof (1 + 2)", 96),
+            ("64 / (sizeof -1)", 8),
+        ];
+        for (expr, value) in cases {
+            let source = format!("int main() {{ return {}; }}", expr);
+            assert_eq!(eval(&parse_source(&source)), Some(value), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn bare_expression_is_parsed_with_precedence() {
+        let expr = Parser::new(crate::lexer::tokenize("1+2*3")).parse_expression();
+        assert_eq!(eval(&expr), Some(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unexpected token after expression: `4`")]
+    fn bare_expression_rejects_trailing_tokens() {
+        Parser::new(crate::lexer::tokenize("1+2*3 4")).parse_expression();
+    }
+}
@@ -0,0 +1,68 @@
+// This module implements an interactive read-eval-print loop for arithmetic expressions.
+// Each line is wrapped in a minimal main function, parsed, and evaluated at compile time.
+//
+// Rust features used:
+// - Generic readers and writers (BufRead/Write) so the loop can be driven by scripts
+// - catch_unwind to keep the loop running after a parse error
+//
+// Functionality:
+// - Reads expressions line by line and prints their constant value
+// - Reports divisions that would trap at runtime instead of exiting
+use std::io::{self, BufRead, Write};
+use std::panic;
+
+use crate::eval::eval;
+use crate::lexer::tokenize;
+use crate::parser::Parser;
+
+/// Runs the REPL until the input is exhausted.
+///
+/// Every line is compiled as `int main(){return <line>;}` and its value is printed.
+/// Lines that fail to parse are reported by the panic hook and skipped. Every expression
+/// that parses is constant, so the only evaluation failures are a division by zero and
+/// `i64::MIN / -1`, which are reported as "error: division by zero / overflow".
+///
+/// # Arguments
+/// * `input` - Source of expression lines.
+/// * `output` - Destination for the prompt and results.
+pub fn run<R: BufRead, W: Write>(input: R, mut output: W) -> io::Result<()> {
+    write!(output, "> ")?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            let source = format!("int main(){{return {};}}", line);
+            // The lexer and parser report errors by panicking; recover and keep going
+            let result = panic::catch_unwind(|| {
+                let mut parser = Parser::new(tokenize(&source));
+                eval(&parser.parse())
+            });
+            match result {
+                Ok(Some(value)) => writeln!(output, "{}", value)?,
+                Ok(None) => writeln!(output, "error: division by zero / overflow")?,
+                Err(_) => {}
+            }
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn scripted_session_prints_each_value() {
+        let mut output = Vec::new();
+        run(Cursor::new("2*(3+4)\n\n1/0\n"), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "> 14\n> > error: division by zero / overflow\n> \n"
+        );
+    }
+}
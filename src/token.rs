@@ -5,13 +5,27 @@
 pub enum Token {
     /// The `int` keyword, used for declaring integer types.
     Int,
+    /// The `void` keyword, used for an empty parameter list or no return value.
+    Void,
+    /// The `char` keyword, used in the `char **argv` parameter of main.
+    Char,
     /// The `return` keyword, used for returning values from functions.
     Return,
+    /// The `register` storage-class keyword. Reserved so it cannot be used as a name;
+    /// not yet accepted anywhere, since there are no declarations to qualify.
+    Register,
+    /// The `auto` storage-class keyword. Reserved so it cannot be used as a name;
+    /// not yet accepted anywhere, since there are no declarations to qualify.
+    Auto,
+    /// The `sizeof` operator keyword.
+    Sizeof,
+    /// The `enum` keyword, used to declare named integer constants.
+    Enum,
     /// An identifier, such as variable or function names.
     /// Contains the identifier's string value.
     Ident(String),
-    /// A numeric literal (integer). Contains the parsed value.
-    Number(i64),
+    /// A numeric literal (integer). Contains the parsed value and its type suffix.
+    Number(i64, IntSuffix),
     /// The plus operator (`+`).
     Plus,
     /// The minus operator (`-`).
@@ -30,5 +44,106 @@ pub enum Token {
     RBrace,
     /// Semicolon (`;`), used to terminate statements.
     Semicolon,
+    /// Comma (`,`), used to separate enumerators.
+    Comma,
+    /// Assignment (`=`), used to give an enumerator an explicit value.
+    Assign,
 }
 
+impl std::fmt::Display for Token {
+    /// Writes the token as it appears in source code.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Int => write!(f, "int"),
+            Token::Void => write!(f, "void"),
+            Token::Char => write!(f, "char"),
+            Token::Return => write!(f, "return"),
+            Token::Register => write!(f, "register"),
+            Token::Auto => write!(f, "auto"),
+            Token::Sizeof => write!(f, "sizeof"),
+            Token::Enum => write!(f, "enum"),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Number(n, suffix) => write!(f, "{}{}", n, suffix),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Star => write!(f, "*"),
+            Token::Slash => write!(f, "/"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::LBrace => write!(f, "{{"),
+            Token::RBrace => write!(f, "}}"),
+            Token::Semicolon => write!(f, ";"),
+            Token::Comma => write!(f, ","),
+            Token::Assign => write!(f, "="),
+        }
+    }
+}
+
+/// Type suffix of an integer literal, e.g. `10L`, `5U` or `3UL`.
+///
+/// The suffix does not change the literal's value; it records the signedness and width
+/// the literal asks for. `LL` is treated like `L`, since both are 64 bits wide here.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct IntSuffix {
+    /// `U`/`u`: the literal is unsigned.
+    pub unsigned: bool,
+    /// `L`/`l` or `LL`/`ll`: the literal is (at least) a long.
+    pub long: bool,
+}
+
+impl IntSuffix {
+    /// Parses the suffix text following an integer literal's digits.
+    /// Accepts an optional `u`/`U` before or after an optional `l`, `L`, `ll` or `LL`.
+    /// Returns None for anything else (e.g. `LUL`, `lL` or `x`).
+    pub fn parse(text: &str) -> Option<IntSuffix> {
+        let (unsigned, rest) = match text.strip_prefix(['u', 'U']) {
+            Some(rest) => (true, rest),
+            None => match text.strip_suffix(['u', 'U']) {
+                Some(rest) => (true, rest),
+                None => (false, text),
+            },
+        };
+        let long = match rest {
+            "" => false,
+            "l" | "L" | "ll" | "LL" => true,
+            _ => return None,
+        };
+        Some(IntSuffix { unsigned, long })
+    }
+}
+
+impl std::fmt::Display for IntSuffix {
+    /// Writes the suffix in its canonical upper-case form (`U`, `L` or `UL`).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.unsigned {
+            write!(f, "U")?;
+        }
+        if self.long {
+            write!(f, "L")?;
+        }
+        Ok(())
+    }
+}
+
+/// Location of a token in the source code.
+///
+/// Lines and columns are 1-based. `column` counts characters, so a tab is a single
+/// column, while `display_column` expands tabs to the next multiple of
+/// `lexer::TAB_WIDTH` so a caret can be placed under the token when rendering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    /// Line on which the token starts.
+    pub line: usize,
+    /// Column of the first character, counting a tab as one character.
+    pub column: usize,
+    /// Column of the first character with tabs expanded.
+    pub display_column: usize,
+    /// Length of the token in characters.
+    pub len: usize,
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
@@ -0,0 +1,94 @@
+// This module provides visitor traits for walking the AST.
+// A pass (folding, warnings, analysis) implements a visitor and overrides only the
+// methods it cares about; the default methods take care of recursing into children.
+//
+// Rust features used:
+// - Traits with default method implementations
+// - Free functions (walk_*) holding the traversal so overrides can still recurse
+//
+// Functionality:
+// - Visitor: read-only traversal over &Expr
+// - VisitorMut: traversal over &mut Expr for passes that rewrite the tree in place
+//   (e.g. constant folding in fold.rs)
+//
+// For example, a visitor counting binary operations overrides visit_expr, increments
+// its counter on Expr::BinaryOp, and then calls walk_expr to continue into the operands.
+//
+// Passes that run after `-O1` folding see each Expr::Folded as a leaf: the walk does not
+// descend into the original expression, which is kept only for codegen annotations.
+// Passes that need the full source tree must run before fold::fold_constants.
+use crate::ast::Expr;
+
+/// A read-only AST visitor.
+///
+/// `Expr::Folded` nodes are leaves; see the module comment.
+pub trait Visitor {
+    /// Called for every expression node. The default implementation visits its children.
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+}
+
+/// Visits the children of an expression (but not the expression itself).
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        // A folded value is a leaf; its original expression is not part of the tree
+        Expr::Number(_) | Expr::Folded { .. } => {}
+        Expr::UnaryOp { operand, .. } => visitor.visit_expr(operand),
+        Expr::BinaryOp { left, right, .. } => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+    }
+}
+
+/// An AST visitor that may modify the nodes it visits.
+///
+/// `Expr::Folded` nodes are leaves; see the module comment.
+pub trait VisitorMut {
+    /// Called for every expression node. The default implementation visits its children.
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr);
+    }
+}
+
+/// Visits the children of an expression mutably (but not the expression itself).
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        // A folded value is a leaf; its original expression is not part of the tree
+        Expr::Number(_) | Expr::Folded { .. } => {}
+        Expr::UnaryOp { operand, .. } => visitor.visit_expr_mut(operand),
+        Expr::BinaryOp { left, right, .. } => {
+            visitor.visit_expr_mut(left);
+            visitor.visit_expr_mut(right);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_source;
+
+    /// The visitor from the module comment: counts binary operations.
+    struct BinaryOpCounter {
+        count: usize,
+    }
+
+    impl Visitor for BinaryOpCounter {
+        fn visit_expr(&mut self, expr: &Expr) {
+            if let Expr::BinaryOp { .. } = expr {
+                self.count += 1;
+            }
+            walk_expr(self, expr);
+        }
+    }
+
+    #[test]
+    fn visitor_counts_binary_operations() {
+        let expr = parse_source("int main() { return 1 + 2 * -(3 - 4) / 5; }");
+        let mut counter = BinaryOpCounter { count: 0 };
+        counter.visit_expr(&expr);
+        assert_eq!(counter.count, 4);
+    }
+}
@@ -0,0 +1,103 @@
+// End-to-end tests that run the compiler binary.
+// Tests that need an external assembler or linker skip themselves when it is missing.
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Runs the compiler binary with the given arguments.
+fn min_cc(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_min_cc")).args(args).output().expect("Failed to run min_cc")
+}
+
+/// Returns true if the given tool can be run, printing a skip notice otherwise.
+fn have_tool(tool: &str) -> bool {
+    let found = Command::new(tool).arg("--version").output().is_ok();
+    if !found {
+        eprintln!("skipping: {} is not installed", tool);
+    }
+    found
+}
+
+/// Creates an empty scratch directory for one test, holding `program` as `main.c`.
+fn scratch_dir(name: &str, program: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("min_cc-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Failed to create scratch directory");
+    fs::write(dir.join("main.c"), program).expect("Failed to write main.c");
+    dir
+}
+
+#[test]
+fn dash_c_writes_an_object_and_does_not_link() {
+    if !have_tool("as") {
+        return;
+    }
+    let dir = scratch_dir("dash-c", "int main() { return 42; }");
+    let source = dir.join("main.c");
+    let object = dir.join("main.o");
+    let output = min_cc(&[
+        "--syntax", "gas-intel", "-c", "-o", object.to_str().unwrap(), source.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // Only the source, the assembly and the object exist: nothing was linked
+    let mut files: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["main.c", "main.o", "main.s"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn dash_c_rejects_an_object_path_that_is_the_assembly_path() {
+    let dir = scratch_dir("dash-c-same-path", "int main() { return 42; }");
+    let source = dir.join("main.c");
+    let asm = dir.join("main.s");
+    let output = min_cc(&["--syntax", "gas-intel", "-c", "-o", asm.to_str().unwrap(), source.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is the assembly file"));
+    assert!(!asm.exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn time_passes_reports_every_phase_without_changing_the_output() {
+    let dir = scratch_dir("time-passes", "int main() { return 2 + 3 * 4; }");
+    let source = dir.join("main.c");
+    let plain = dir.join("plain.asm");
+    let timed = dir.join("timed.asm");
+    let output = min_cc(&["-O1", "-o", plain.to_str().unwrap(), source.to_str().unwrap()]);
+    assert!(output.status.success());
+    let output = min_cc(&["-O1", "--time-passes", "-o", timed.to_str().unwrap(), source.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    let report = String::from_utf8_lossy(&output.stderr);
+    for phase in ["lexing", "parsing", "optimization", "codegen", "total"] {
+        assert!(report.lines().any(|line| line.starts_with(phase)), "no {} row in:\n{}", phase, report);
+    }
+    assert_eq!(fs::read(&plain).unwrap(), fs::read(&timed).unwrap());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn expr_program_exits_with_the_value_of_the_expression() {
+    if !have_tool("as") || !have_tool("ld") {
+        return;
+    }
+    let dir = scratch_dir("expr", "1+2*3");
+    let source = dir.join("main.c");
+    let object = dir.join("main.o");
+    let executable = dir.join("main");
+    let output = min_cc(&[
+        "--expr", "--syntax", "gas-intel", "-c", "-o", object.to_str().unwrap(), source.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let status = Command::new("ld").arg(&object).arg("-o").arg(&executable).status().unwrap();
+    assert!(status.success());
+    let status = Command::new(&executable).status().unwrap();
+    assert_eq!(status.code(), Some(7));
+    fs::remove_dir_all(&dir).unwrap();
+}